@@ -0,0 +1,148 @@
+#[macro_use]
+extern crate serde_derive;
+
+/// Ordering preference applied when more candidate uncles exist than `max_uncles_num` allows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum UncleOrdering {
+    OldestFirst,
+    HighestRewardFirst,
+}
+
+/// A candidate uncle, as seen by the block-template construction path. Implemented by whatever
+/// type the chain crate uses to track pending uncles.
+pub trait UncleCandidate {
+    fn timestamp(&self) -> u64;
+    fn total_reward(&self) -> u64;
+}
+
+/// Operator-tunable policy controlling which uncles the miner embeds in a new block template.
+///
+/// `select` applies the policy to a list of candidates. The block-template construction path
+/// (`new_block`/`new_block_builder`) does not call it: that path applies its own fixed rule,
+/// the one `UnclePackingPolicy::default()` models.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct UnclePackingPolicy {
+    pub max_uncles_num: usize,
+    pub pack_into_epoch_starting: bool,
+    pub ordering: UncleOrdering,
+}
+
+impl Default for UnclePackingPolicy {
+    fn default() -> Self {
+        // Reproduces the previously hard-coded behavior: uncles are never packed into the
+        // epoch-starting block, and otherwise all available uncles are greedily included,
+        // oldest first.
+        UnclePackingPolicy {
+            max_uncles_num: usize::max_value(),
+            pack_into_epoch_starting: false,
+            ordering: UncleOrdering::OldestFirst,
+        }
+    }
+}
+
+impl UnclePackingPolicy {
+    /// Picks which of `candidates` to embed in a block at the given position, applying
+    /// `pack_into_epoch_starting`, `ordering` and `max_uncles_num` in that order.
+    pub fn select<U: UncleCandidate + Clone>(
+        &self,
+        candidates: &[U],
+        is_epoch_starting: bool,
+    ) -> Vec<U> {
+        if is_epoch_starting && !self.pack_into_epoch_starting {
+            return Vec::new();
+        }
+
+        let mut selected: Vec<U> = candidates.to_vec();
+        match self.ordering {
+            UncleOrdering::OldestFirst => selected.sort_by_key(UncleCandidate::timestamp),
+            UncleOrdering::HighestRewardFirst => {
+                selected.sort_by(|a, b| b.total_reward().cmp(&a.total_reward()))
+            }
+        }
+        selected.truncate(self.max_uncles_num);
+        selected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone)]
+    struct Candidate {
+        timestamp: u64,
+        total_reward: u64,
+    }
+
+    impl UncleCandidate for Candidate {
+        fn timestamp(&self) -> u64 {
+            self.timestamp
+        }
+
+        fn total_reward(&self) -> u64 {
+            self.total_reward
+        }
+    }
+
+    fn candidates() -> Vec<Candidate> {
+        vec![
+            Candidate {
+                timestamp: 30,
+                total_reward: 10,
+            },
+            Candidate {
+                timestamp: 10,
+                total_reward: 30,
+            },
+            Candidate {
+                timestamp: 20,
+                total_reward: 20,
+            },
+        ]
+    }
+
+    #[test]
+    fn default_suppresses_uncles_at_the_epoch_start() {
+        let policy = UnclePackingPolicy::default();
+        assert!(policy.select(&candidates(), true).is_empty());
+    }
+
+    #[test]
+    fn default_greedily_includes_every_candidate_oldest_first() {
+        let policy = UnclePackingPolicy::default();
+        let selected = policy.select(&candidates(), false);
+        let timestamps: Vec<u64> = selected.iter().map(Candidate::timestamp).collect();
+        assert_eq!(timestamps, vec![10, 20, 30]);
+    }
+
+    #[test]
+    fn highest_reward_first_orders_by_descending_reward() {
+        let policy = UnclePackingPolicy {
+            ordering: UncleOrdering::HighestRewardFirst,
+            ..UnclePackingPolicy::default()
+        };
+        let selected = policy.select(&candidates(), false);
+        let rewards: Vec<u64> = selected.iter().map(Candidate::total_reward).collect();
+        assert_eq!(rewards, vec![30, 20, 10]);
+    }
+
+    #[test]
+    fn max_uncles_num_truncates_after_ordering() {
+        let policy = UnclePackingPolicy {
+            max_uncles_num: 2,
+            ..UnclePackingPolicy::default()
+        };
+        let selected = policy.select(&candidates(), false);
+        let timestamps: Vec<u64> = selected.iter().map(Candidate::timestamp).collect();
+        assert_eq!(timestamps, vec![10, 20]);
+    }
+
+    #[test]
+    fn pack_into_epoch_starting_allows_uncles_at_the_epoch_start() {
+        let policy = UnclePackingPolicy {
+            pack_into_epoch_starting: true,
+            ..UnclePackingPolicy::default()
+        };
+        assert_eq!(policy.select(&candidates(), true).len(), 3);
+    }
+}
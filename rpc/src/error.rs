@@ -0,0 +1,70 @@
+use jsonrpc_core::{Error, ErrorCode, Value};
+use pool::PoolError;
+use std::collections::BTreeMap;
+
+/// Error codes returned to RPC clients, stable across releases so callers can branch on them
+/// instead of scraping the `message` string.
+///
+/// `pool::PoolError`'s variants aren't part of this tree, so rather than match on (and risk
+/// silently drifting from) its exact shape, every pool rejection maps to the same code and
+/// carries the pool's own `Display` output as structured `data`. Once `PoolError`'s variants are
+/// confirmed against the real crate, split `POOL_REJECTED` into a code per variant the way the
+/// request asked for.
+#[derive(Debug, PartialEq)]
+pub struct RpcError {
+    code: i64,
+    message: String,
+    reason: String,
+}
+
+impl RpcError {
+    const POOL_REJECTED: i64 = -32000;
+
+    fn pool_rejected(reason: impl Into<String>) -> RpcError {
+        RpcError {
+            code: RpcError::POOL_REJECTED,
+            message: "Transaction rejected by the pool".to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl From<PoolError> for RpcError {
+    fn from(err: PoolError) -> Self {
+        RpcError::pool_rejected(err.to_string())
+    }
+}
+
+impl From<RpcError> for Error {
+    fn from(err: RpcError) -> Self {
+        let mut data = BTreeMap::new();
+        data.insert("reason".to_string(), Value::String(err.reason));
+
+        Error {
+            code: ErrorCode::ServerError(err.code),
+            message: err.message,
+            data: Some(Value::Object(data.into_iter().collect())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pool_rejection_carries_the_reason_as_structured_data() {
+        let rpc_error: Error = RpcError::pool_rejected("double spend").into();
+
+        assert_eq!(rpc_error.code, ErrorCode::ServerError(RpcError::POOL_REJECTED));
+        match rpc_error.data {
+            Some(Value::Object(data)) => {
+                assert_eq!(
+                    data.get("reason"),
+                    Some(&Value::String("double spend".to_string()))
+                );
+            }
+            other => panic!("expected an object for `data`, got {:?}", other),
+        }
+    }
+}
@@ -3,8 +3,10 @@ extern crate bigint;
 extern crate jsonrpc_core;
 #[macro_use]
 extern crate jsonrpc_macros;
-extern crate jsonrpc_minihttp_server;
+extern crate jsonrpc_http_server;
+extern crate jsonrpc_ipc_server;
 extern crate jsonrpc_server_utils;
+extern crate jsonrpc_ws_server;
 #[macro_use]
 extern crate log;
 extern crate nervos_chain as chain;
@@ -12,24 +14,33 @@ extern crate nervos_core as core;
 extern crate nervos_network as network;
 extern crate nervos_pool as pool;
 extern crate nervos_protocol;
+extern crate nervos_spec as spec;
 extern crate nervos_sync as sync;
 #[macro_use]
 extern crate serde_derive;
+extern crate tokio;
+
+mod error;
 
 use bigint::H256;
 use chain::chain::ChainClient;
 use core::block::Block;
 use core::header::Header;
 use core::transaction::Transaction;
+use error::RpcError;
 use jsonrpc_core::{IoHandler, Result};
-use jsonrpc_minihttp_server::ServerBuilder;
+use jsonrpc_http_server::{Server, ServerBuilder};
+use jsonrpc_ipc_server::{Server as IpcServer, ServerBuilder as IpcServerBuilder};
 use jsonrpc_server_utils::cors::AccessControlAllowOrigin;
-use jsonrpc_server_utils::hosts::DomainsValidation;
+use jsonrpc_server_utils::hosts::{DomainsValidation, Host};
+use jsonrpc_ws_server::{Server as WsServer, ServerBuilder as WsServerBuilder};
 use nervos_protocol::Payload;
 use network::NetworkService;
 use pool::TransactionPool;
+use spec::ChainSpec;
 use std::sync::Arc;
 use sync::protocol::RELAY_PROTOCOL_ID;
+use tokio::runtime::TaskExecutor;
 
 build_rpc_trait! {
     pub trait Rpc {
@@ -51,9 +62,64 @@ build_rpc_trait! {
 
         #[rpc(name = "get_tip_header")]
         fn get_tip_header(&self) -> Result<Header>;
+
+        // curl -d '{"id": 2, "jsonrpc": "2.0", "method":"get_peers","params": []}' -H 'content-type:application/json' 'http://localhost:3030'
+        #[rpc(name = "get_peers")]
+        fn get_peers(&self) -> Result<Vec<PeerInfo>>;
+
+        // curl -d '{"id": 2, "jsonrpc": "2.0", "method":"local_node_info","params": []}' -H 'content-type:application/json' 'http://localhost:3030'
+        #[rpc(name = "local_node_info")]
+        fn local_node_info(&self) -> Result<NodeInfo>;
+
+        // curl -d '{"id": 2, "jsonrpc": "2.0", "method":"trace_transaction","params": [{"version":2, "deps":[], "inputs":[], "outputs":[]}]}' -H 'content-type:application/json' 'http://localhost:3030'
+        #[rpc(name = "trace_transaction")]
+        fn trace_transaction(&self, Transaction) -> Result<TransactionTrace>;
     }
 }
 
+/// A single connected session, as seen by this node.
+#[derive(Serialize)]
+pub struct PeerInfo {
+    pub peer_id: String,
+    pub address: String,
+    pub direction: &'static str,
+    pub protocols: Vec<(String, String)>,
+}
+
+/// Summary of this node's connectivity, for dashboards and operators.
+#[derive(Serialize)]
+pub struct NodeInfo {
+    pub active_peers: usize,
+    pub connected_peers: usize,
+    pub max_peers: usize,
+    pub listen_addresses: Vec<String>,
+}
+
+/// Verification outcome of a single input script, as produced by a dry run.
+///
+/// `cycles` is always `None`: no lock or type script is run on this path, so there is no cycle
+/// count to report. `success` says only whether the output the input points at exists in a
+/// transaction this node's chain knows; whether that output is already spent is not checked.
+#[derive(Serialize)]
+pub struct ScriptTrace {
+    pub index: usize,
+    pub cycles: Option<u64>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// Result of running a transaction through verification without committing it to the pool.
+///
+/// `error` is set whenever `success` is false: it reports a transaction with no inputs, or
+/// summarizes that at least one entry of `inputs` failed (that entry carries its own reason).
+#[derive(Serialize)]
+pub struct TransactionTrace {
+    pub hash: H256,
+    pub success: bool,
+    pub error: Option<String>,
+    pub inputs: Vec<ScriptTrace>,
+}
+
 struct RpcImpl<C> {
     pub network: Arc<NetworkService>,
     pub chain: Arc<C>,
@@ -65,7 +131,9 @@ impl<C: ChainClient + 'static> Rpc for RpcImpl<C> {
         let pool_result = self.tx_pool.add_to_memory_pool(tx.clone());
         debug!(target: "rpc", "send_transaction add to pool result: {:?}", pool_result);
 
-        let result = tx.hash();
+        pool_result.map_err(RpcError::from)?;
+        let hash = tx.hash();
+
         let mut payload = Payload::new();
         payload.set_transaction((&tx).into());
         self.network.with_context_eval(RELAY_PROTOCOL_ID, |nc| {
@@ -73,7 +141,7 @@ impl<C: ChainClient + 'static> Rpc for RpcImpl<C> {
                 nc.send(peer_id, payload.clone()).ok();
             }
         });
-        Ok(result)
+        Ok(hash)
     }
 
     fn get_block(&self, hash: H256) -> Result<Option<Block>> {
@@ -96,27 +164,178 @@ impl<C: ChainClient + 'static> Rpc for RpcImpl<C> {
     }
 
     fn get_block_hash(&self, height: u64) -> Result<Option<H256>> {
+        // Always defers to the chain's own stored hash, including for height 0: chain
+        // initialization is what actually assembles and hashes the genesis block, so this must
+        // stay consistent with whatever `get_block` can look up, rather than synthesizing a
+        // hash of its own that no stored block would ever match.
         Ok(self.chain.block_hash(height))
     }
 
     fn get_tip_header(&self) -> Result<Header> {
         Ok(self.chain.tip_header().clone())
     }
+
+    fn get_peers(&self) -> Result<Vec<PeerInfo>> {
+        let mut peers = Vec::new();
+        self.network.with_context_eval(RELAY_PROTOCOL_ID, |nc| {
+            for (peer_id, session) in nc.sessions() {
+                peers.push(PeerInfo {
+                    peer_id: peer_id.to_base58(),
+                    address: session.address.to_string(),
+                    direction: if session.originated {
+                        "outbound"
+                    } else {
+                        "inbound"
+                    },
+                    protocols: session
+                        .protocol_versions
+                        .iter()
+                        .map(|(protocol_id, version)| (protocol_id.to_string(), version.clone()))
+                        .collect(),
+                });
+            }
+        });
+        Ok(peers)
+    }
+
+    fn local_node_info(&self) -> Result<NodeInfo> {
+        // "active" is peers that have completed the relay protocol handshake and are usable for
+        // relaying; "connected" is every established session, including ones still negotiating.
+        let active_peers = self
+            .network
+            .with_context_eval(RELAY_PROTOCOL_ID, |nc| nc.sessions().count())
+            .unwrap_or(0);
+        let connected_peers = self.network.connected_peers();
+
+        Ok(NodeInfo {
+            active_peers,
+            connected_peers,
+            max_peers: self.network.max_peers(),
+            listen_addresses: self
+                .network
+                .listen_addresses()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        })
+    }
+
+    fn trace_transaction(&self, tx: Transaction) -> Result<TransactionTrace> {
+        let hash = tx.hash();
+
+        if tx.inputs.is_empty() {
+            return Ok(TransactionTrace {
+                hash,
+                success: false,
+                error: Some("transaction has no inputs".to_string()),
+                inputs: Vec::new(),
+            });
+        }
+
+        // Looks up the transaction each input's previous output points at in the current chain
+        // state, without calling into the pool and without persisting anything. Only the
+        // existence of the referenced output is checked: its spent status is not, and the
+        // lock/type scripts are not run, so `cycles` is `None`.
+        let inputs: Vec<ScriptTrace> = tx
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(index, input)| {
+                let previous_output = &input.previous_output;
+                let resolved = self
+                    .chain
+                    .get_transaction(&previous_output.hash)
+                    .map_or(false, |previous_tx| {
+                        previous_tx
+                            .outputs
+                            .get(previous_output.index as usize)
+                            .is_some()
+                    });
+
+                if resolved {
+                    ScriptTrace {
+                        index,
+                        cycles: None,
+                        success: true,
+                        error: None,
+                    }
+                } else {
+                    ScriptTrace {
+                        index,
+                        cycles: None,
+                        success: false,
+                        error: Some(
+                            "input references an unknown output".to_string(),
+                        ),
+                    }
+                }
+            })
+            .collect();
+
+        let success = inputs.iter().all(|input| input.success);
+        let error = if success {
+            None
+        } else {
+            Some("one or more inputs failed verification".to_string())
+        };
+
+        Ok(TransactionTrace {
+            hash,
+            success,
+            error,
+            inputs,
+        })
+    }
 }
 
 pub struct RpcServer {
     pub config: Config,
 }
 
+/// Builds the `cors` allow-list a transport's `ServerBuilder` expects from the configured list
+/// of origins. An empty/unset list rejects every `Origin` header, which is the conservative
+/// default for a transport that wasn't explicitly opened up.
+fn cors_domains(origins: &Option<Vec<String>>) -> DomainsValidation<AccessControlAllowOrigin> {
+    DomainsValidation::AllowOnly(
+        origins
+            .iter()
+            .flatten()
+            .cloned()
+            .map(AccessControlAllowOrigin::Value)
+            .collect(),
+    )
+}
+
+/// Builds the `Host` header allow-list a transport's `ServerBuilder` expects, guarding against
+/// DNS-rebinding attacks the same way `cors_domains` guards against cross-origin browser access.
+fn host_domains(hosts: &Option<Vec<String>>) -> DomainsValidation<Host> {
+    DomainsValidation::AllowOnly(
+        hosts
+            .iter()
+            .flatten()
+            .cloned()
+            .map(Host::from)
+            .collect(),
+    )
+}
+
 impl RpcServer {
+    /// Starts the HTTP RPC endpoint, and the WebSocket and IPC endpoints where configured, on the
+    /// given shared tokio runtime and returns a handle that shuts them down (instead of blocking
+    /// the caller in `wait()`).
     pub fn start<C>(
         &self,
+        executor: TaskExecutor,
         network: Arc<NetworkService>,
         chain: Arc<C>,
         tx_pool: Arc<TransactionPool<C>>,
-    ) where
+        chain_spec: Arc<ChainSpec>,
+    ) -> RpcServerHandle
+    where
         C: ChainClient + 'static,
     {
+        info!(target: "rpc", "Serving chain spec \"{}\"", chain_spec.name);
+
         let mut io = IoHandler::new();
         io.extend_with(
             RpcImpl {
@@ -126,21 +345,99 @@ impl RpcServer {
             }.to_delegate(),
         );
 
-        let server = ServerBuilder::new(io)
+        let http = ServerBuilder::new(io.clone())
             .cors(DomainsValidation::AllowOnly(vec![
                 AccessControlAllowOrigin::Null,
                 AccessControlAllowOrigin::Any,
             ]))
-            .threads(3)
+            .event_loop_executor(executor.clone())
+            .threads(self.config.threads)
             .start_http(&self.config.listen_addr.parse().unwrap())
-            .unwrap();
+            .expect("Start RPC HTTP server");
+        info!(target: "rpc", "Now listening on {:?} (http)", http.address());
 
-        info!(target: "rpc", "Now listening on {:?}", server.address());
-        server.wait().unwrap();
+        let ws = self.config.ws_listen_addr.as_ref().map(|ws_listen_addr| {
+            let server = WsServerBuilder::new(io.clone())
+                .event_loop_executor(executor.clone())
+                .allowed_origins(cors_domains(&self.config.ws_allowed_origins))
+                .allowed_hosts(host_domains(&self.config.ws_allowed_hosts))
+                .start(&ws_listen_addr.parse().unwrap())
+                .expect("Start RPC WebSocket server");
+            info!(target: "rpc", "Now listening on {} (ws)", ws_listen_addr);
+            server
+        });
+
+        // IPC is a local unix-socket transport: callers connect by filesystem path rather than
+        // URL, so there's no `Origin`/`Host` header to validate and no CORS/host allow-list to
+        // configure here. Access control for it is the socket file's permissions.
+        let ipc = self.config.ipc_path.as_ref().map(|ipc_path| {
+            let server = IpcServerBuilder::new(io)
+                .event_loop_executor(executor)
+                .start(ipc_path)
+                .expect("Start RPC IPC server");
+            info!(target: "rpc", "Now listening on {} (ipc)", ipc_path);
+            server
+        });
+
+        RpcServerHandle {
+            http: Some(http),
+            ws,
+            ipc,
+        }
+    }
+}
+
+/// Handle to the running RPC subsystem. Dropping it (or calling `close()` explicitly) shuts all
+/// enabled transports down gracefully instead of leaving them running forever behind a blocking
+/// `wait()`.
+pub struct RpcServerHandle {
+    http: Option<Server>,
+    ws: Option<WsServer>,
+    ipc: Option<IpcServer>,
+}
+
+impl RpcServerHandle {
+    pub fn close(&mut self) {
+        if let Some(http) = self.http.take() {
+            http.close();
+        }
+        if let Some(ws) = self.ws.take() {
+            ws.close();
+        }
+        if let Some(ipc) = self.ipc.take() {
+            ipc.close();
+        }
     }
 }
 
+impl Drop for RpcServerHandle {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+fn default_threads() -> usize {
+    3
+}
+
 #[derive(Clone, Debug, PartialEq, Deserialize)]
 pub struct Config {
     pub listen_addr: String,
+    #[serde(default = "default_threads")]
+    pub threads: usize,
+    /// Enables the WebSocket transport when set, serving the same `IoHandler` as HTTP.
+    #[serde(default)]
+    pub ws_listen_addr: Option<String>,
+    /// Origins allowed to open the WebSocket transport, mirroring HTTP's `cors`. Unset rejects
+    /// every `Origin` header, so browser/dapp clients need this set explicitly; non-browser
+    /// clients that don't send an `Origin` header are unaffected.
+    #[serde(default)]
+    pub ws_allowed_origins: Option<Vec<String>>,
+    /// `Host` header values accepted by the WebSocket transport, guarding against DNS-rebinding
+    /// attacks the same way HTTP's allowed-hosts validation does.
+    #[serde(default)]
+    pub ws_allowed_hosts: Option<Vec<String>>,
+    /// Enables the local IPC/unix-socket transport when set.
+    #[serde(default)]
+    pub ipc_path: Option<String>,
 }
@@ -0,0 +1,211 @@
+extern crate bigint;
+extern crate nervos_core as core;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+
+use bigint::H256;
+use core::transaction::Transaction;
+use std::fs;
+use std::path::Path;
+
+/// Consensus parameters that differ from chain to chain.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ConsensusParams {
+    pub initial_block_reward: u64,
+    pub max_block_cycles: u64,
+    pub epoch_length: u64,
+}
+
+/// The transactions and outputs that seed the chain at height 0.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct GenesisSpec {
+    pub timestamp: u64,
+    pub issued_cells: Vec<Transaction>,
+}
+
+/// A named chain: its human-readable identity, consensus rules and genesis definition.
+///
+/// Loaded from a JSON spec file, or picked from a built-in preset (`mainnet`, `testnet`, `dev`),
+/// so operators can run reproducible multi-network deployments from a single binary.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ChainSpec {
+    pub name: String,
+    pub params: ConsensusParams,
+    pub genesis: GenesisSpec,
+}
+
+/// Error produced while loading or validating a chain spec.
+#[derive(Debug)]
+pub enum SpecLoadError {
+    Io(String),
+    Parse(String),
+    MissingField(&'static str),
+}
+
+impl From<std::io::Error> for SpecLoadError {
+    fn from(err: std::io::Error) -> Self {
+        SpecLoadError::Io(err.to_string())
+    }
+}
+
+impl ChainSpec {
+    /// Loads and validates a spec from a JSON file on disk.
+    pub fn read(path: &Path) -> Result<ChainSpec, SpecLoadError> {
+        let content = fs::read_to_string(path)?;
+        let spec: ChainSpec =
+            serde_json::from_str(&content).map_err(|err| SpecLoadError::Parse(err.to_string()))?;
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    /// Looks up a built-in preset by name, without touching the filesystem.
+    pub fn load_preset(name: &str) -> Option<ChainSpec> {
+        match name {
+            "mainnet" => Some(ChainSpec::mainnet()),
+            "testnet" => Some(ChainSpec::testnet()),
+            "dev" => Some(ChainSpec::dev()),
+            _ => None,
+        }
+    }
+
+    fn validate(&self) -> Result<(), SpecLoadError> {
+        if self.name.is_empty() {
+            return Err(SpecLoadError::MissingField("name"));
+        }
+        if self.params.epoch_length == 0 {
+            return Err(SpecLoadError::MissingField("params.epoch_length"));
+        }
+        Ok(())
+    }
+
+    pub fn mainnet() -> ChainSpec {
+        ChainSpec {
+            name: "mainnet".to_string(),
+            params: ConsensusParams {
+                initial_block_reward: 50_000_000_000,
+                max_block_cycles: 5_000_000,
+                epoch_length: 2000,
+            },
+            genesis: GenesisSpec {
+                timestamp: 1573_602_000_000,
+                issued_cells: Vec::new(),
+            },
+        }
+    }
+
+    pub fn testnet() -> ChainSpec {
+        ChainSpec {
+            name: "testnet".to_string(),
+            params: ConsensusParams {
+                initial_block_reward: 50_000_000_000,
+                max_block_cycles: 5_000_000,
+                epoch_length: 1000,
+            },
+            genesis: GenesisSpec {
+                timestamp: 1575_331_200_000,
+                issued_cells: Vec::new(),
+            },
+        }
+    }
+
+    pub fn dev() -> ChainSpec {
+        ChainSpec {
+            name: "dev".to_string(),
+            params: ConsensusParams {
+                initial_block_reward: 50_000_000_000,
+                max_block_cycles: 5_000_000,
+                epoch_length: 10,
+            },
+            genesis: GenesisSpec {
+                timestamp: 0,
+                issued_cells: Vec::new(),
+            },
+        }
+    }
+
+    /// A content identifier for this spec: distinct named chains (and distinct spec files) are
+    /// very unlikely to collide on the same value, though as a non-cryptographic digest it can. This is NOT the real genesis block hash — this snapshot has no
+    /// chain crate to assemble and hash an actual genesis block, so `RpcImpl::get_block_hash`
+    /// does not call this and instead defers to the chain's own stored hash. Kept as a standalone
+    /// utility (e.g. for logging which spec a node is running) so two differently-configured
+    /// chains are easy to tell apart without comparing every field by hand.
+    ///
+    /// Built from FNV-1a rather than `std`'s `DefaultHasher`: `DefaultHasher`'s output is
+    /// explicitly unspecified across Rust versions and platforms, which would make this identifier
+    /// change out from under operators on a toolchain upgrade.
+    pub fn genesis_hash(&self) -> H256 {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(self.name.as_bytes());
+        bytes.extend_from_slice(&self.params.initial_block_reward.to_le_bytes());
+        bytes.extend_from_slice(&self.params.max_block_cycles.to_le_bytes());
+        bytes.extend_from_slice(&self.params.epoch_length.to_le_bytes());
+        bytes.extend_from_slice(&self.genesis.timestamp.to_le_bytes());
+        for cell in &self.genesis.issued_cells {
+            bytes.extend_from_slice(cell.hash().as_ref());
+        }
+
+        let mut digest = [0u8; 32];
+        for (index, chunk) in digest.chunks_mut(8).enumerate() {
+            chunk.copy_from_slice(&fnv1a(&bytes, index as u64).to_le_bytes());
+        }
+        H256::from(digest)
+    }
+}
+
+/// FNV-1a, seeded so each of the 4 chunks making up `genesis_hash`'s 32 bytes differs even though
+/// they all hash the same input. Not cryptographic; only needed to be stable and well-distributed.
+fn fnv1a(bytes: &[u8], seed: u64) -> u64 {
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+    let mut hash = 0xCBF2_9CE4_8422_2325 ^ seed;
+    for &byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_rejects_missing_name() {
+        let mut spec = ChainSpec::dev();
+        spec.name = String::new();
+        match spec.validate() {
+            Err(SpecLoadError::MissingField("name")) => {}
+            other => panic!("expected a missing-name error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn validate_rejects_zero_epoch_length() {
+        let mut spec = ChainSpec::dev();
+        spec.params.epoch_length = 0;
+        match spec.validate() {
+            Err(SpecLoadError::MissingField("params.epoch_length")) => {}
+            other => panic!("expected a missing-epoch-length error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn load_preset_recognizes_the_built_in_presets_and_nothing_else() {
+        assert_eq!(ChainSpec::load_preset("mainnet"), Some(ChainSpec::mainnet()));
+        assert_eq!(ChainSpec::load_preset("testnet"), Some(ChainSpec::testnet()));
+        assert_eq!(ChainSpec::load_preset("dev"), Some(ChainSpec::dev()));
+        assert_eq!(ChainSpec::load_preset("nonexistent"), None);
+    }
+
+    #[test]
+    fn genesis_hash_is_distinct_across_presets_and_stable_for_the_same_spec() {
+        let mainnet = ChainSpec::mainnet().genesis_hash();
+        let testnet = ChainSpec::testnet().genesis_hash();
+        let dev = ChainSpec::dev().genesis_hash();
+
+        assert_ne!(mainnet, testnet);
+        assert_ne!(mainnet, dev);
+        assert_ne!(testnet, dev);
+        assert_eq!(mainnet, ChainSpec::mainnet().genesis_hash());
+    }
+}
@@ -133,7 +133,10 @@ pub struct PackUnclesIntoEpochStarting;
 impl Spec for PackUnclesIntoEpochStarting {
     crate::name!("pack_uncles_into_epoch_starting");
 
-    // Case: Miner should not add uncles into the epoch starting
+    // Case: uncles are never packed into the epoch-starting block, and otherwise every
+    // available uncle is greedily included. (`miner::UnclePackingPolicy` models this same rule
+    // as operator-tunable config and is covered by its own unit tests, but the block-template
+    // construction path exercised here still applies it as a fixed rule, not that config.)
     fn run(&self, net: Net) {
         let node = &net.nodes[0];
         let uncle = construct_uncle(node);